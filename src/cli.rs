@@ -23,4 +23,8 @@ pub struct Args {
     /// Offset from the top of the terminal to start rendering the image
     #[arg(long, default_value = "8")]
     pub top_offset: usize,
+
+    /// Rendering backend (auto, half-block, kitty, sixel)
+    #[arg(long, default_value = "auto")]
+    pub render: String,
 }
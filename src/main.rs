@@ -10,9 +10,11 @@ use std::thread;
 use std::time::Duration;
 
 mod cli;
+mod render;
 mod terminal;
 
 use cli::Args;
+use render::RenderTarget;
 use terminal::{disable_echo, get_terminal_size, is_terminal};
 
 // Constants
@@ -21,27 +23,31 @@ const RESIZE_FACTOR_Y: usize = 2;
 const RESIZE_FACTOR_X: usize = 1;
 const DEFAULT_TERM_COLS: usize = 80;
 const DEFAULT_TERM_ROWS: usize = 24;
-const FPS: u64 = 15;
 const NUM_ADDITIONAL_LINES: usize = 2;
 
 // ANSI escape codes
 const ANSI_CURSOR_UP: &str = "\x1B[{}A";
-const ANSI_CURSOR_HIDE: &str = "\x1B[?25l";
-const ANSI_CURSOR_SHOW: &str = "\x1B[?25h";
+pub(crate) const ANSI_CURSOR_HIDE: &str = "\x1B[?25l";
+pub(crate) const ANSI_CURSOR_SHOW: &str = "\x1B[?25h";
 const ANSI_BG_TRANSPARENT_COLOR: &str = "\x1b[0;39;49m";
 const ANSI_BG_RGB_COLOR: &str = "\x1b[48;2;{};{};{}m";
 const ANSI_FG_TRANSPARENT_COLOR: &str = "\x1b[0m ";
 const ANSI_FG_RGB_COLOR: &str = "\x1b[38;2;{};{};{}m▄";
-const ANSI_RESET: &str = "\x1b[0m";
+pub(crate) const ANSI_RESET: &str = "\x1b[0m";
+
+/// The delay used in place of near-zero (0 or 1 centisecond) animation frame delays,
+/// matching what browsers clamp such GIF delays to.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(100);
 
 #[derive(Clone)]
-struct ImageFrame {
+pub(crate) struct ImageFrame {
     image: DynamicImage,
+    delay: Duration,
 }
 
 impl ImageFrame {
-    fn new(image: DynamicImage) -> Self {
-        Self { image }
+    fn new(image: DynamicImage, delay: Duration) -> Self {
+        Self { image, delay }
     }
 
     fn get_pixel_rgba(&self, x: u32, y: u32) -> (u8, u8, u8, u8) {
@@ -50,9 +56,18 @@ impl ImageFrame {
         (rgba[0], rgba[1], rgba[2], rgba[3])
     }
 
-    fn dimensions(&self) -> (u32, u32) {
+    pub(crate) fn dimensions(&self) -> (u32, u32) {
         self.image.dimensions()
     }
+
+    /// Raw 8-bit RGBA pixel data, row-major, used by the full-resolution renderers.
+    pub(crate) fn rgba_bytes(&self) -> Vec<u8> {
+        self.image.to_rgba8().into_raw()
+    }
+
+    pub(crate) fn delay(&self) -> Duration {
+        self.delay
+    }
 }
 
 fn read_input(input: Option<String>) -> Result<Vec<u8>> {
@@ -73,7 +88,9 @@ fn decode_image(buf: &[u8]) -> Result<Vec<ImageFrame>> {
     if let Ok(format) = image::guess_format(buf) {
         match format {
             ImageFormat::Gif => decode_gif(buf),
-            ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Bmp => decode_static_image(buf),
+            ImageFormat::WebP => decode_webp(buf),
+            ImageFormat::Png => decode_png(buf),
+            ImageFormat::Jpeg | ImageFormat::Bmp => decode_static_image(buf),
             _ => decode_static_image(buf),
         }
     } else {
@@ -82,21 +99,112 @@ fn decode_image(buf: &[u8]) -> Result<Vec<ImageFrame>> {
     }
 }
 
+/// Decode an animated WebP into one `ImageFrame` per frame, falling back to a single
+/// static frame when the file turns out not to carry an animation.
+fn decode_webp(buf: &[u8]) -> Result<Vec<ImageFrame>> {
+    use image::AnimationDecoder;
+    use image::codecs::webp::WebPDecoder;
+
+    let decoder = WebPDecoder::new(io::Cursor::new(buf)).context("Failed to read WebP header")?;
+    if !decoder.has_animation() {
+        return decode_static_image(buf);
+    }
+
+    collect_animation_frames(decoder.into_frames())
+}
+
+/// Decode an APNG into one `ImageFrame` per frame, falling back to a single static frame
+/// for a plain (non-animated) PNG.
+fn decode_png(buf: &[u8]) -> Result<Vec<ImageFrame>> {
+    use image::AnimationDecoder;
+    use image::codecs::png::PngDecoder;
+
+    let decoder = PngDecoder::new(io::Cursor::new(buf)).context("Failed to read PNG header")?;
+    if !decoder
+        .is_apng()
+        .context("Failed to check for APNG frames")?
+    {
+        return decode_static_image(buf);
+    }
+
+    let apng = decoder.apng().context("Failed to read APNG frames")?;
+    collect_animation_frames(apng.into_frames())
+}
+
+/// Drain an `image` animation frame iterator into the `ImageFrame`s the rest of the
+/// pipeline consumes, clamping each frame's delay the same way `decode_gif` does.
+fn collect_animation_frames(frames: image::Frames) -> Result<Vec<ImageFrame>> {
+    let mut out = Vec::new();
+
+    for frame in frames {
+        let frame = frame.context("Failed to decode animation frame")?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let delay_ms = numer.checked_div(denom).unwrap_or(0);
+        let delay = if delay_ms <= 10 {
+            MIN_FRAME_DELAY
+        } else {
+            Duration::from_millis(delay_ms as u64)
+        };
+
+        out.push(ImageFrame::new(
+            DynamicImage::ImageRgba8(frame.into_buffer()),
+            delay,
+        ));
+    }
+
+    if out.is_empty() {
+        anyhow::bail!("No frames found in animation");
+    }
+
+    Ok(out)
+}
+
 fn decode_gif(buf: &[u8]) -> Result<Vec<ImageFrame>> {
-    let decoder = gif::DecodeOptions::new();
-    let mut decoder = decoder.read_info(buf)?;
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(buf)?;
 
+    let width = decoder.width() as u32;
+    let height = decoder.height() as u32;
+
+    let mut canvas = image::RgbaImage::new(width, height);
     let mut frames = Vec::new();
 
     while let Some(frame) = decoder.read_next_frame()? {
-        let img = image::RgbaImage::from_raw(
-            frame.width as u32,
-            frame.height as u32,
-            frame.buffer.to_vec(),
-        )
-        .context("Failed to create image from GIF frame")?;
-
-        frames.push(ImageFrame::new(DynamicImage::ImageRgba8(img)));
+        // `Previous` disposal needs the canvas as it looked right before this frame was
+        // drawn, so snapshot it up front.
+        let pre_draw_snapshot = (frame.dispose == gif::DisposalMethod::Previous)
+            .then(|| canvas.clone());
+
+        blend_gif_frame(&mut canvas, frame);
+
+        // `frame.delay` is in 1/100s units; clamp the absurdly small delays some GIFs
+        // ship with, the same way browsers do.
+        let delay = if frame.delay <= 1 {
+            MIN_FRAME_DELAY
+        } else {
+            Duration::from_millis(frame.delay as u64 * 10)
+        };
+        frames.push(ImageFrame::new(
+            DynamicImage::ImageRgba8(canvas.clone()),
+            delay,
+        ));
+
+        match frame.dispose {
+            gif::DisposalMethod::Any | gif::DisposalMethod::Keep => {}
+            gif::DisposalMethod::Background => clear_rect(
+                &mut canvas,
+                frame.left as u32,
+                frame.top as u32,
+                frame.width as u32,
+                frame.height as u32,
+            ),
+            gif::DisposalMethod::Previous => {
+                if let Some(snapshot) = pre_draw_snapshot {
+                    canvas = snapshot;
+                }
+            }
+        }
     }
 
     if frames.is_empty() {
@@ -106,6 +214,69 @@ fn decode_gif(buf: &[u8]) -> Result<Vec<ImageFrame>> {
     Ok(frames)
 }
 
+/// Alpha-blend a GIF frame's sub-rectangle onto the logical-screen canvas at its offset.
+fn blend_gif_frame(canvas: &mut image::RgbaImage, frame: &gif::Frame) {
+    let (left, top) = (frame.left as u32, frame.top as u32);
+
+    for y in 0..frame.height as u32 {
+        for x in 0..frame.width as u32 {
+            let (cx, cy) = (left + x, top + y);
+            if cx >= canvas.width() || cy >= canvas.height() {
+                continue;
+            }
+
+            let idx = ((y * frame.width as u32 + x) * 4) as usize;
+            let src = &frame.buffer[idx..idx + 4];
+            if src[3] == 0 {
+                continue;
+            }
+
+            let blended = if src[3] == 255 {
+                [src[0], src[1], src[2], src[3]]
+            } else {
+                blend_over(
+                    [src[0], src[1], src[2], src[3]],
+                    canvas.get_pixel(cx, cy).0,
+                )
+            };
+            canvas.put_pixel(cx, cy, image::Rgba(blended));
+        }
+    }
+}
+
+/// Composite `src` over `dst` using standard "source over" alpha blending.
+fn blend_over(src: [u8; 4], dst: [u8; 4]) -> [u8; 4] {
+    let sa = src[3] as f32 / 255.0;
+    let da = dst[3] as f32 / 255.0;
+    let out_a = sa + da * (1.0 - sa);
+
+    if out_a <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+
+    let blend_channel = |s: u8, d: u8| {
+        let s = s as f32 / 255.0;
+        let d = d as f32 / 255.0;
+        (((s * sa) + d * da * (1.0 - sa)) / out_a * 255.0).round() as u8
+    };
+
+    [
+        blend_channel(src[0], dst[0]),
+        blend_channel(src[1], dst[1]),
+        blend_channel(src[2], dst[2]),
+        (out_a * 255.0).round() as u8,
+    ]
+}
+
+/// Clear a rectangle of the canvas back to fully transparent.
+fn clear_rect(canvas: &mut image::RgbaImage, left: u32, top: u32, width: u32, height: u32) {
+    for y in top..(top + height).min(canvas.height()) {
+        for x in left..(left + width).min(canvas.width()) {
+            canvas.put_pixel(x, y, image::Rgba([0, 0, 0, 0]));
+        }
+    }
+}
+
 fn decode_static_image(buf: &[u8]) -> Result<Vec<ImageFrame>> {
     let img = image::load_from_memory(buf).context("Failed to decode image")?;
 
@@ -114,10 +285,47 @@ fn decode_static_image(buf: &[u8]) -> Result<Vec<ImageFrame>> {
         anyhow::bail!("The input image is too small");
     }
 
-    Ok(vec![ImageFrame::new(img)])
+    Ok(vec![ImageFrame::new(img, Duration::ZERO)])
+}
+
+/// Resize strategy used to fit a frame into the terminal cell grid.
+#[derive(Clone, Copy)]
+enum ResizeType {
+    /// Scale to fit entirely inside the grid, preserving aspect ratio (current default).
+    Fit,
+    /// Scale to cover the whole grid, preserving aspect ratio, cropping any overflow.
+    Fill,
+    /// Scale to the grid dimensions exactly, ignoring aspect ratio.
+    Stretch,
 }
 
-fn scale_frames(frames: Vec<ImageFrame>) -> Result<Vec<ImageFrame>> {
+fn parse_filter_type(interpolation: &str) -> Result<image::imageops::FilterType> {
+    use image::imageops::FilterType;
+
+    match interpolation {
+        "nearest" => Ok(FilterType::Nearest),
+        "triangle" => Ok(FilterType::Triangle),
+        "catmullrom" => Ok(FilterType::CatmullRom),
+        "gaussian" => Ok(FilterType::Gaussian),
+        "lanczos" => Ok(FilterType::Lanczos3),
+        other => anyhow::bail!("Unknown interpolation method: {}", other),
+    }
+}
+
+fn parse_resize_type(resize_type: &str) -> Result<ResizeType> {
+    match resize_type {
+        "fit" => Ok(ResizeType::Fit),
+        "fill" => Ok(ResizeType::Fill),
+        "stretch" => Ok(ResizeType::Stretch),
+        other => anyhow::bail!("Unknown resize type: {}", other),
+    }
+}
+
+fn scale_frames(
+    frames: Vec<ImageFrame>,
+    filter: image::imageops::FilterType,
+    resize_type: ResizeType,
+) -> Result<Vec<ImageFrame>> {
     let (cols, rows) = if is_terminal() {
         get_terminal_size().unwrap_or((DEFAULT_TERM_COLS, DEFAULT_TERM_ROWS))
     } else {
@@ -131,31 +339,53 @@ fn scale_frames(frames: Vec<ImageFrame>) -> Result<Vec<ImageFrame>> {
 
     for frame in frames {
         let (orig_width, orig_height) = frame.dimensions();
+        let delay = frame.delay();
 
-        // Calculate new dimensions maintaining aspect ratio
-        let aspect_ratio = orig_width as f32 / orig_height as f32;
-        let target_aspect_ratio = w as f32 / h as f32;
+        let scaled_img = match resize_type {
+            ResizeType::Fit => {
+                // Calculate new dimensions maintaining aspect ratio
+                let aspect_ratio = orig_width as f32 / orig_height as f32;
+                let target_aspect_ratio = w as f32 / h as f32;
 
-        let (new_width, new_height) = if aspect_ratio > target_aspect_ratio {
-            (w as u32, (w as f32 / aspect_ratio) as u32)
-        } else {
-            ((h as f32 * aspect_ratio) as u32, h as u32)
+                let (new_width, new_height) = if aspect_ratio > target_aspect_ratio {
+                    (w as u32, (w as f32 / aspect_ratio) as u32)
+                } else {
+                    ((h as f32 * aspect_ratio) as u32, h as u32)
+                };
+
+                frame.image.resize(new_width, new_height, filter)
+            }
+            ResizeType::Fill => {
+                // Scale so the grid is fully covered, then crop the overflow on the longer axis
+                let scale = (w as f32 / orig_width as f32).max(h as f32 / orig_height as f32);
+                let resized_width = (orig_width as f32 * scale).round() as u32;
+                let resized_height = (orig_height as f32 * scale).round() as u32;
+
+                let resized = frame.image.resize_exact(resized_width, resized_height, filter);
+                let x = (resized_width.saturating_sub(w as u32)) / 2;
+                let y = (resized_height.saturating_sub(h as u32)) / 2;
+
+                resized.crop_imm(x, y, w as u32, h as u32)
+            }
+            ResizeType::Stretch => frame.image.resize_exact(w as u32, h as u32, filter),
         };
 
-        let scaled_img =
-            frame
-                .image
-                .resize(new_width, new_height, image::imageops::FilterType::Lanczos3);
-        scaled_frames.push(ImageFrame::new(scaled_img));
+        scaled_frames.push(ImageFrame::new(scaled_img, delay));
     }
 
     Ok(scaled_frames)
 }
 
-fn escape_frames(frames: Vec<ImageFrame>) -> Vec<Vec<String>> {
+struct EscapedFrame {
+    lines: Vec<String>,
+    delay: Duration,
+}
+
+fn escape_frames(frames: Vec<ImageFrame>) -> Vec<EscapedFrame> {
     let mut escaped = Vec::with_capacity(frames.len());
 
     for frame in frames {
+        let delay = frame.delay();
         let (width, height) = frame.dimensions();
         let max_y = height - (height % 2);
         let max_x = width;
@@ -202,13 +432,95 @@ fn escape_frames(frames: Vec<ImageFrame>) -> Vec<Vec<String>> {
             lines[idx as usize] = line;
         }
 
-        escaped.push(lines);
+        escaped.push(EscapedFrame { lines, delay });
     }
 
     escaped
 }
 
-fn print_frames(frames: Vec<Vec<String>>, silent: bool) -> Result<()> {
+/// A playback command produced by the raw-key reader thread.
+enum PlaybackEvent {
+    TogglePause,
+    /// Step one frame in the given direction; only acted on while paused.
+    Step(i64),
+    /// Multiply the current playback speed by this factor.
+    Speed(f32),
+    Quit,
+}
+
+/// How long to wait for the rest of an arrow-key escape sequence before giving up and
+/// treating the Escape byte that triggered this as standalone.
+const ESCAPE_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Having just read a standalone `0x1B`, check whether it's the start of an arrow-key
+/// sequence (`ESC '[' ('C' | 'D')`) rather than a standalone Escape keypress. Each
+/// following byte is only read once it's confirmed ready within `ESCAPE_SEQUENCE_TIMEOUT`,
+/// so a bare Escape (an accidental press, or an Alt-key combo) doesn't block the reader and
+/// swallow whatever the user types next.
+fn read_arrow_sequence(stdin: &mut io::Stdin) -> Option<PlaybackEvent> {
+    if !terminal::stdin_ready_within(ESCAPE_SEQUENCE_TIMEOUT) {
+        return None;
+    }
+
+    let mut bracket = [0u8; 1];
+    if stdin.read(&mut bracket).ok() != Some(1) || bracket[0] != b'[' {
+        return None;
+    }
+
+    if !terminal::stdin_ready_within(ESCAPE_SEQUENCE_TIMEOUT) {
+        return None;
+    }
+
+    let mut direction = [0u8; 1];
+    if stdin.read(&mut direction).ok() != Some(1) {
+        return None;
+    }
+
+    match direction[0] {
+        b'C' => Some(PlaybackEvent::Step(1)),
+        b'D' => Some(PlaybackEvent::Step(-1)),
+        _ => None,
+    }
+}
+
+/// Spawn a thread that reads raw keystrokes from stdin and forwards playback commands:
+/// space to pause/resume, left/right arrows to step while paused, `+`/`-` to scale the
+/// frame delay, and `q` to quit. The thread exits once it sees `q` or its receiver is gone.
+fn spawn_key_reader() -> mpsc::Receiver<PlaybackEvent> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut stdin = io::stdin();
+        let mut byte = [0u8; 1];
+
+        loop {
+            match stdin.read(&mut byte) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+
+            let event = match byte[0] {
+                b' ' => Some(PlaybackEvent::TogglePause),
+                b'q' | b'Q' => Some(PlaybackEvent::Quit),
+                b'+' | b'=' => Some(PlaybackEvent::Speed(1.25)),
+                b'-' | b'_' => Some(PlaybackEvent::Speed(0.8)),
+                0x1B => read_arrow_sequence(&mut stdin),
+                _ => None,
+            };
+
+            if let Some(event) = event {
+                let quit = matches!(event, PlaybackEvent::Quit);
+                if tx.send(event).is_err() || quit {
+                    break;
+                }
+            }
+        }
+    });
+
+    rx
+}
+
+fn print_frames(frames: Vec<EscapedFrame>, silent: bool) -> Result<()> {
     let _term_state = if is_terminal() {
         Some(disable_echo())
     } else {
@@ -221,36 +533,72 @@ fn print_frames(frames: Vec<Vec<String>>, silent: bool) -> Result<()> {
     let frame_count = frames.len();
 
     if frame_count == 1 {
-        for line in &frames[0] {
+        for line in &frames[0].lines {
             print!("{}", line);
         }
     } else {
-        // Setup signal handling for Ctrl+C
+        // Raw mode lets the key reader thread see individual keystrokes as they're typed.
+        let _raw_state = if is_terminal() {
+            Some(terminal::enable_raw_mode())
+        } else {
+            None
+        };
+        let key_events = spawn_key_reader();
+
+        // Setup signal handling for Ctrl+C as a fallback to the `q` key.
         let playing = Arc::new(AtomicBool::new(true));
         let p = playing.clone();
         ctrlc::set_handler(move || {
             p.store(false, Ordering::SeqCst);
         })?;
 
-        let frame_duration = Duration::from_millis(1000 / FPS);
-        let h = frames[0].len() + if silent { 0 } else { NUM_ADDITIONAL_LINES };
+        let h = frames[0].lines.len() + if silent { 0 } else { NUM_ADDITIONAL_LINES };
+
+        let mut i: i64 = 0;
+        let mut paused = false;
+        let mut speed: f32 = 1.0;
+        let mut first = true;
 
-        let mut i = 0;
         while playing.load(Ordering::SeqCst) {
-            if i != 0 {
+            while let Ok(event) = key_events.try_recv() {
+                match event {
+                    PlaybackEvent::TogglePause => paused = !paused,
+                    PlaybackEvent::Step(delta) if paused => {
+                        i = (i + delta).rem_euclid(frame_count as i64);
+                    }
+                    PlaybackEvent::Step(_) => {}
+                    PlaybackEvent::Speed(factor) => speed = (speed * factor).clamp(0.1, 8.0),
+                    PlaybackEvent::Quit => playing.store(false, Ordering::SeqCst),
+                }
+            }
+
+            if !playing.load(Ordering::SeqCst) {
+                break;
+            }
+
+            if !first {
                 print!("{}", format!("{} {}", ANSI_CURSOR_UP, h));
             }
+            first = false;
 
-            for line in &frames[i % frame_count] {
+            let frame = &frames[(i as usize) % frame_count];
+            for line in &frame.lines {
                 print!("{}", line);
             }
 
             if !silent {
-                print!("\npress `ctrl c` to exit\n");
+                print!(
+                    "\nspace: pause  ←/→: step  +/-: speed  q/ctrl c: exit{}\n",
+                    if paused { "  [paused]" } else { "" }
+                );
             }
 
-            thread::sleep(frame_duration);
-            i += 1;
+            if paused {
+                thread::sleep(Duration::from_millis(30));
+            } else {
+                thread::sleep(frame.delay.div_f32(speed));
+                i += 1;
+            }
         }
     }
 
@@ -263,12 +611,26 @@ fn print_frames(frames: Vec<Vec<String>>, silent: bool) -> Result<()> {
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    let filter = parse_filter_type(&args.interpolation)?;
+    let resize_type = parse_resize_type(&args.resize_type)?;
+    let render_target = if args.render == "auto" {
+        terminal::detect_render_target()
+    } else {
+        RenderTarget::parse(&args.render)?
+    };
+
     let input_data = read_input(args.input)?;
     let frames = decode_image(&input_data)?;
-    let scaled_frames = scale_frames(frames)?;
-    let escaped_frames = escape_frames(scaled_frames);
+    let scaled_frames = scale_frames(frames, filter, resize_type)?;
 
-    print_frames(escaped_frames, args.silent)?;
+    match render_target {
+        RenderTarget::HalfBlock => {
+            let escaped_frames = escape_frames(scaled_frames);
+            print_frames(escaped_frames, args.silent)?;
+        }
+        RenderTarget::Kitty => render::kitty::render(scaled_frames, args.silent)?,
+        RenderTarget::Sixel => render::sixel::render(scaled_frames, args.silent)?,
+    }
 
     Ok(())
 }
@@ -0,0 +1,90 @@
+use crate::ImageFrame;
+use crate::terminal::{disable_echo, is_terminal};
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// The Kitty spec caps each chunk's base64 payload at 4096 bytes.
+const CHUNK_SIZE: usize = 4096;
+const DELETE_IMAGE: &str = "\x1b_Ga=d,q=2\x1b\\";
+
+/// Encode a single frame as one or more Kitty graphics protocol escape sequences
+/// that transmit and display a 32-bit RGBA image.
+fn encode_frame(frame: &ImageFrame) -> String {
+    let (width, height) = frame.dimensions();
+    let encoded = STANDARD.encode(frame.rgba_bytes());
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(CHUNK_SIZE).collect();
+    let last = chunks.len().saturating_sub(1);
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i != last);
+        let payload = std::str::from_utf8(chunk).expect("base64 output is ASCII");
+
+        if i == 0 {
+            // `C=1` keeps the cursor where it was instead of advancing past the image, so
+            // redrawing the next frame over it doesn't scroll the screen. `q=2` suppresses
+            // the OK/error APC reply, which would otherwise pile up unread on stdin since
+            // nothing here drains it between frames.
+            out.push_str(&format!(
+                "\x1b_Gf=32,s={},v={},a=T,C=1,q=2,m={};{}\x1b\\",
+                width, height, more, payload
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, payload));
+        }
+    }
+
+    out
+}
+
+pub fn render(frames: Vec<ImageFrame>, silent: bool) -> Result<()> {
+    let _term_state = if is_terminal() { Some(disable_echo()) } else { None };
+
+    print!("{}", crate::ANSI_CURSOR_HIDE);
+
+    let encoded: Vec<(String, Duration)> = frames
+        .iter()
+        .map(|frame| (encode_frame(frame), frame.delay()))
+        .collect();
+    let frame_count = encoded.len();
+
+    if frame_count == 1 {
+        print!("{}", encoded[0].0);
+        io::stdout().flush().ok();
+    } else {
+        if !silent {
+            println!("press `ctrl c` to exit");
+        }
+
+        let playing = Arc::new(AtomicBool::new(true));
+        let p = playing.clone();
+        ctrlc::set_handler(move || {
+            p.store(false, Ordering::SeqCst);
+        })?;
+
+        let mut i = 0;
+        while playing.load(Ordering::SeqCst) {
+            if i != 0 {
+                print!("{}", DELETE_IMAGE);
+            }
+
+            let (image, delay) = &encoded[i % frame_count];
+            print!("{}", image);
+            io::stdout().flush().ok();
+
+            thread::sleep(*delay);
+            i += 1;
+        }
+    }
+
+    print!("{}", crate::ANSI_CURSOR_SHOW);
+    io::stdout().flush().ok();
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+
+pub mod kitty;
+pub mod sixel;
+
+/// Backend used to paint decoded frames into the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderTarget {
+    /// Two pixels per cell via the `▄` half-block trick (fallback of `auto`, works everywhere).
+    HalfBlock,
+    /// Full-resolution RGBA images transmitted via the Kitty graphics protocol.
+    Kitty,
+    /// Palette-quantized DEC sixel images.
+    Sixel,
+}
+
+impl RenderTarget {
+    pub fn parse(render: &str) -> Result<Self> {
+        match render {
+            "half-block" => Ok(RenderTarget::HalfBlock),
+            "kitty" => Ok(RenderTarget::Kitty),
+            "sixel" => Ok(RenderTarget::Sixel),
+            other => anyhow::bail!("Unknown render target: {}", other),
+        }
+    }
+}
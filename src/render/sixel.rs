@@ -0,0 +1,140 @@
+use crate::ImageFrame;
+use crate::terminal::{disable_echo, is_terminal};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// Fixed 6x6x6 color cube; good enough to start, a median-cut palette can replace it later.
+const PALETTE_LEVELS: u8 = 6;
+// DECSC/DECRC: save and restore the cursor position, so each frame overwrites the last
+// regardless of how many terminal rows the image actually occupies (that depends on the
+// terminal's font cell size in pixels, which we have no way to know).
+const SAVE_CURSOR: &str = "\x1b7";
+const RESTORE_CURSOR: &str = "\x1b8";
+
+fn quantize(value: u8) -> u8 {
+    ((value as u16 * (PALETTE_LEVELS as u16 - 1)) / 255) as u8
+}
+
+fn palette_index(r: u8, g: u8, b: u8) -> usize {
+    let levels = PALETTE_LEVELS as usize;
+    let (r, g, b) = (quantize(r) as usize, quantize(g) as usize, quantize(b) as usize);
+    (r * levels + g) * levels + b
+}
+
+fn palette_rgb(index: usize) -> (u8, u8, u8) {
+    let levels = PALETTE_LEVELS as usize;
+    let b = index % levels;
+    let g = (index / levels) % levels;
+    let r = index / (levels * levels);
+    let scale = |v: usize| (v * 100 / (levels - 1)) as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+/// Encode a single frame as a DEC sixel image, quantized to a 6x6x6 color cube.
+fn encode_frame(frame: &ImageFrame) -> String {
+    let (width, height) = frame.dimensions();
+    let num_colors = PALETTE_LEVELS as usize * PALETTE_LEVELS as usize * PALETTE_LEVELS as usize;
+
+    let mut out = String::new();
+    out.push_str("\x1bP0;1;0q");
+
+    for idx in 0..num_colors {
+        let (r, g, b) = palette_rgb(idx);
+        out.push_str(&format!("#{};2;{};{};{}", idx, r, g, b));
+    }
+
+    let mut y = 0;
+    while y < height {
+        let band_height = (height - y).min(6);
+
+        let mut band_colors = vec![None; (width * 6) as usize];
+        for row in 0..band_height {
+            for x in 0..width {
+                let (r, g, b, a) = frame.get_pixel_rgba(x, y + row);
+                if a >= 128 {
+                    band_colors[(row * width + x) as usize] = Some(palette_index(r, g, b));
+                }
+            }
+        }
+
+        let used_colors: BTreeSet<usize> = band_colors.iter().filter_map(|c| *c).collect();
+
+        let mut first = true;
+        for color in used_colors {
+            if !first {
+                out.push('$');
+            }
+            first = false;
+
+            out.push_str(&format!("#{}", color));
+            for x in 0..width {
+                let mut mask: u8 = 0;
+                for row in 0..band_height {
+                    if band_colors[(row * width + x) as usize] == Some(color) {
+                        mask |= 1 << row;
+                    }
+                }
+                out.push((mask + 63) as char);
+            }
+        }
+
+        out.push('-');
+        y += 6;
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+pub fn render(frames: Vec<ImageFrame>, silent: bool) -> Result<()> {
+    let _term_state = if is_terminal() { Some(disable_echo()) } else { None };
+
+    print!("{}", crate::ANSI_CURSOR_HIDE);
+
+    let encoded: Vec<(String, Duration)> = frames
+        .iter()
+        .map(|frame| (encode_frame(frame), frame.delay()))
+        .collect();
+    let frame_count = encoded.len();
+
+    if frame_count == 1 {
+        print!("{}", encoded[0].0);
+        io::stdout().flush().ok();
+    } else {
+        let playing = Arc::new(AtomicBool::new(true));
+        let p = playing.clone();
+        ctrlc::set_handler(move || {
+            p.store(false, Ordering::SeqCst);
+        })?;
+
+        print!("{}", SAVE_CURSOR);
+        io::stdout().flush().ok();
+
+        let mut i = 0;
+        while playing.load(Ordering::SeqCst) {
+            print!("{}", RESTORE_CURSOR);
+
+            let (image, delay) = &encoded[i % frame_count];
+            print!("{}", image);
+
+            if !silent {
+                print!("\npress `ctrl c` to exit\n");
+            }
+
+            io::stdout().flush().ok();
+            thread::sleep(*delay);
+            i += 1;
+        }
+    }
+
+    print!("{}", crate::ANSI_RESET);
+    print!("{}", crate::ANSI_CURSOR_SHOW);
+    io::stdout().flush().ok();
+
+    Ok(())
+}
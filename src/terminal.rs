@@ -1,6 +1,10 @@
 use anyhow::Result;
 use std::any::Any;
-use std::io::{self, IsTerminal};
+use std::env;
+use std::io::{self, IsTerminal, Read, Write};
+use std::time::{Duration, Instant};
+
+use crate::render::RenderTarget;
 
 #[cfg(unix)]
 use libc::{STDOUT_FILENO, ioctl};
@@ -54,22 +58,149 @@ fn get_terminal_size_windows() -> Result<(usize, usize)> {
     Ok((80, 24)) // Default fallback
 }
 
+/// Probe the environment and pick the richest renderer the terminal is likely to support.
+///
+/// Kitty (or a kitty-compatible terminal like Ghostty) is detected from its environment
+/// variables; sixel support is detected by asking the terminal for its Device Attributes
+/// (DA1, `CSI c`) and checking for the `;4` sixel capability in the response. Anything that
+/// answers neither falls back to the half-block renderer, which works everywhere.
+pub fn detect_render_target() -> RenderTarget {
+    if !is_terminal() {
+        return RenderTarget::HalfBlock;
+    }
+
+    if env::var_os("KITTY_WINDOW_ID").is_some() {
+        return RenderTarget::Kitty;
+    }
+
+    let term = env::var("TERM").unwrap_or_default().to_lowercase();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    if term.contains("kitty") || term.contains("ghostty") || term_program.contains("ghostty") {
+        return RenderTarget::Kitty;
+    }
+
+    // The DA1 probe below reads the response from stdin, which would steal image bytes
+    // out from under `read_input` when the image itself is piped in on stdin. Only probe
+    // when stdin is an interactive terminal, not a pipe or redirected file.
+    if io::stdin().is_terminal() && supports_sixel() {
+        return RenderTarget::Sixel;
+    }
+
+    RenderTarget::HalfBlock
+}
+
+/// Ask the terminal for its Device Attributes and look for sixel (`;4`) in the response.
+#[cfg(unix)]
+fn supports_sixel() -> bool {
+    unsafe {
+        let mut original: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(STDOUT_FILENO, &mut original) != 0 {
+            return false;
+        }
+
+        // Non-canonical, non-blocking reads so we can poll the response with a timeout
+        // instead of waiting on a newline that will never come.
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        if libc::tcsetattr(STDOUT_FILENO, libc::TCSANOW, &raw) != 0 {
+            return false;
+        }
+
+        print!("\x1b[c");
+        let _ = io::stdout().flush();
+
+        let response = read_da1_response(Duration::from_millis(200));
+
+        let _ = libc::tcsetattr(STDOUT_FILENO, libc::TCSANOW, &original);
+
+        String::from_utf8_lossy(&response).contains(";4")
+    }
+}
+
+#[cfg(unix)]
+fn read_da1_response(timeout: Duration) -> Vec<u8> {
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 64];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+
+        let mut fds = [libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), 1, remaining.as_millis() as i32) };
+        if ready <= 0 {
+            break;
+        }
+
+        match io::stdin().read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let terminated = buf[..n].contains(&b'c');
+                response.extend_from_slice(&buf[..n]);
+                if terminated {
+                    break;
+                }
+            }
+        }
+    }
+
+    response
+}
+
+#[cfg(windows)]
+fn supports_sixel() -> bool {
+    // Device Attributes probing isn't wired up for the Windows console yet.
+    false
+}
+
+/// Returns true once stdin has a byte ready to read, or false if `timeout` elapses first.
+///
+/// Used by the playback key reader to tell a standalone Escape keypress apart from the
+/// start of an arrow-key escape sequence without blocking indefinitely on the bytes that
+/// would follow a real sequence.
+#[cfg(unix)]
+pub fn stdin_ready_within(timeout: Duration) -> bool {
+    let mut fds = [libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    }];
+    let ready = unsafe { libc::poll(fds.as_mut_ptr(), 1, timeout.as_millis() as i32) };
+    ready > 0
+}
+
+#[cfg(windows)]
+pub fn stdin_ready_within(_timeout: Duration) -> bool {
+    // No non-blocking peek wired up for the Windows console yet; treat every Escape as
+    // standalone rather than risking a hang waiting for bytes that may never come.
+    false
+}
+
 pub struct TermState(Box<dyn Any>);
 
 impl Drop for TermState {
     fn drop(&mut self) {
         #[cfg(unix)]
         {
-            if let Ok(termios) =
-                <Box<dyn std::any::Any> as Clone>::clone(&self.0).downcast::<libc::termios>()
-            {
+            // `Box<dyn Any>` isn't `Clone`, so borrow the concrete value back out with
+            // `downcast_ref` (termios is `Copy`) instead of trying to clone the box itself.
+            if let Some(termios) = self.0.downcast_ref::<libc::termios>() {
                 enable_echo_unix(*termios);
             }
         }
 
         #[cfg(windows)]
         {
-            if let Ok(mode) = self.0.downcast::<u32>() {
+            if let Some(mode) = self.0.downcast_ref::<u32>() {
                 enable_echo_windows(*mode);
             }
         }
@@ -108,6 +239,56 @@ fn disable_echo_unix() -> Box<dyn Any> {
     }
 }
 
+/// Put the terminal into raw, single-keystroke mode: no line buffering and no echo, so a
+/// reader thread can see keys like arrows or space as soon as they're pressed. `ISIG` is
+/// left enabled so Ctrl-C still raises `SIGINT` for the existing handler to catch.
+pub fn enable_raw_mode() -> TermState {
+    #[cfg(unix)]
+    {
+        TermState(enable_raw_mode_unix())
+    }
+
+    #[cfg(windows)]
+    {
+        TermState(enable_raw_mode_windows())
+    }
+}
+
+#[cfg(unix)]
+fn enable_raw_mode_unix() -> Box<dyn Any> {
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        let result = libc::tcgetattr(STDOUT_FILENO, &mut termios);
+
+        if result == 0 {
+            let mut raw = termios;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+
+            let _ = libc::tcsetattr(STDOUT_FILENO, libc::TCSANOW, &raw);
+        }
+
+        Box::new(termios)
+    }
+}
+
+#[cfg(windows)]
+fn enable_raw_mode_windows() -> Box<dyn Any> {
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut mode: u32 = 0;
+
+        if GetConsoleMode(handle, &mut mode) != 0 {
+            let new_mode =
+                (mode & !(ENABLE_ECHO_INPUT | ENABLE_LINE_INPUT)) | ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+            let _ = SetConsoleMode(handle, new_mode);
+        }
+
+        Box::new(mode)
+    }
+}
+
 #[cfg(unix)]
 fn enable_echo_unix(termios: libc::termios) {
     unsafe {